@@ -0,0 +1,258 @@
+//! A small parser-combinator layer in the spirit of Bodil's post, adapted to this crate's
+//! `(text, position)` cursor so callers can build grammars by composing functions instead of
+//! hand-threading `(text, position, _) = ...?;` through every production.
+
+use crate::ParseError;
+
+pub type ParseResult<'a, Output> = Result<(&'a str, usize, Output), ParseError>;
+
+/// Something that can consume a prefix of `input` starting at byte offset `pos` and produce an
+/// `Output`, or fail with a [`ParseError`].
+pub trait Parser<'a, Output> {
+    fn parse(&self, input: &'a str, pos: usize) -> ParseResult<'a, Output>;
+
+    fn map<F, NewOutput>(self, map_fn: F) -> BoxedParser<'a, NewOutput>
+    where
+        Self: Sized + 'a,
+        Output: 'a,
+        NewOutput: 'a,
+        F: Fn(Output) -> NewOutput + 'a,
+    {
+        BoxedParser::new(map(self, map_fn))
+    }
+
+    fn and_then<F, NewOutput, NextParser>(self, f: F) -> BoxedParser<'a, NewOutput>
+    where
+        Self: Sized + 'a,
+        Output: 'a,
+        NewOutput: 'a,
+        NextParser: Parser<'a, NewOutput> + 'a,
+        F: Fn(Output) -> NextParser + 'a,
+    {
+        BoxedParser::new(and_then(self, f))
+    }
+
+    fn pred<F>(self, pred_fn: F) -> BoxedParser<'a, Output>
+    where
+        Self: Sized + 'a,
+        Output: 'a,
+        F: Fn(&Output) -> bool + 'a,
+    {
+        BoxedParser::new(pred(self, pred_fn))
+    }
+}
+
+impl<'a, F, Output> Parser<'a, Output> for F
+where
+    F: Fn(&'a str, usize) -> ParseResult<'a, Output>,
+{
+    fn parse(&self, input: &'a str, pos: usize) -> ParseResult<'a, Output> {
+        self(input, pos)
+    }
+}
+
+/// A type-erased [`Parser`], used to give the default trait methods above a concrete return
+/// type without naming the closure they wrap.
+pub struct BoxedParser<'a, Output> {
+    parser: Box<dyn Parser<'a, Output> + 'a>,
+}
+
+impl<'a, Output> BoxedParser<'a, Output> {
+    pub fn new<P>(parser: P) -> Self
+    where
+        P: Parser<'a, Output> + 'a,
+    {
+        BoxedParser {
+            parser: Box::new(parser),
+        }
+    }
+}
+
+impl<'a, Output> Parser<'a, Output> for BoxedParser<'a, Output> {
+    fn parse(&self, input: &'a str, pos: usize) -> ParseResult<'a, Output> {
+        self.parser.parse(input, pos)
+    }
+}
+
+pub fn map<'a, P, F, A, B>(parser: P, map_fn: F) -> impl Parser<'a, B>
+where
+    P: Parser<'a, A>,
+    F: Fn(A) -> B,
+{
+    move |input, pos| {
+        parser
+            .parse(input, pos)
+            .map(|(next_input, next_pos, result)| (next_input, next_pos, map_fn(result)))
+    }
+}
+
+pub fn and_then<'a, P, F, A, B, NextP>(parser: P, f: F) -> impl Parser<'a, B>
+where
+    P: Parser<'a, A>,
+    NextP: Parser<'a, B>,
+    F: Fn(A) -> NextP,
+{
+    move |input, pos| {
+        parser
+            .parse(input, pos)
+            .and_then(|(next_input, next_pos, result)| f(result).parse(next_input, next_pos))
+    }
+}
+
+/// Succeeds with the same output as `parser` iff `predicate` accepts it.
+pub fn pred<'a, P, A, F>(parser: P, predicate: F) -> impl Parser<'a, A>
+where
+    P: Parser<'a, A>,
+    F: Fn(&A) -> bool,
+{
+    move |input, pos| match parser.parse(input, pos) {
+        Ok(result) if predicate(&result.2) => Ok(result),
+        _ => Err(ParseError::NoValueFound(pos)),
+    }
+}
+
+/// Runs `parser1` then `parser2`, keeping both outputs.
+pub fn pair<'a, P1, P2, R1, R2>(parser1: P1, parser2: P2) -> impl Parser<'a, (R1, R2)>
+where
+    P1: Parser<'a, R1>,
+    P2: Parser<'a, R2>,
+{
+    move |input, pos| {
+        let (next_input, next_pos, result1) = parser1.parse(input, pos)?;
+        let (final_input, final_pos, result2) = parser2.parse(next_input, next_pos)?;
+        Ok((final_input, final_pos, (result1, result2)))
+    }
+}
+
+/// Runs `parser1` then `parser2`, keeping only the first output.
+pub fn left<'a, P1, P2, R1, R2>(parser1: P1, parser2: P2) -> impl Parser<'a, R1>
+where
+    P1: Parser<'a, R1>,
+    P2: Parser<'a, R2>,
+    R1: 'a,
+    R2: 'a,
+{
+    map(pair(parser1, parser2), |(left, _right)| left)
+}
+
+/// Runs `parser1` then `parser2`, keeping only the second output.
+pub fn right<'a, P1, P2, R1, R2>(parser1: P1, parser2: P2) -> impl Parser<'a, R2>
+where
+    P1: Parser<'a, R1>,
+    P2: Parser<'a, R2>,
+    R1: 'a,
+    R2: 'a,
+{
+    map(pair(parser1, parser2), |(_left, right)| right)
+}
+
+/// Tries `parser1`; if it fails, tries `parser2` against the original input.
+pub fn either<'a, P1, P2, A>(parser1: P1, parser2: P2) -> impl Parser<'a, A>
+where
+    P1: Parser<'a, A>,
+    P2: Parser<'a, A>,
+{
+    move |input, pos| match parser1.parse(input, pos) {
+        ok @ Ok(_) => ok,
+        Err(_) => parser2.parse(input, pos),
+    }
+}
+
+/// Applies `parser` as many times as possible (including zero), collecting the outputs.
+pub fn zero_or_more<'a, P, A>(parser: P) -> impl Parser<'a, Vec<A>>
+where
+    P: Parser<'a, A>,
+{
+    move |mut input, mut pos| {
+        let mut result = Vec::new();
+        while let Ok((next_input, next_pos, next_item)) = parser.parse(input, pos) {
+            input = next_input;
+            pos = next_pos;
+            result.push(next_item);
+        }
+        Ok((input, pos, result))
+    }
+}
+
+/// Like [`zero_or_more`], but fails unless `parser` succeeds at least once.
+pub fn one_or_more<'a, P, A>(parser: P) -> impl Parser<'a, Vec<A>>
+where
+    P: Parser<'a, A>,
+{
+    move |input, pos| {
+        let (mut input, mut pos, first_item) = parser.parse(input, pos)?;
+        let mut result = vec![first_item];
+
+        while let Ok((next_input, next_pos, next_item)) = parser.parse(input, pos) {
+            input = next_input;
+            pos = next_pos;
+            result.push(next_item);
+        }
+
+        Ok((input, pos, result))
+    }
+}
+
+/// Matches the literal string `expected` at the cursor.
+pub fn match_literal<'a>(expected: &'static str) -> impl Parser<'a, String> {
+    move |input: &'a str, pos: usize| crate::literal(input, pos, expected)
+}
+
+/// Consumes and returns the next character, or fails with [`ParseError::PrematureEndOfText`].
+pub fn any_char(input: &str, pos: usize) -> ParseResult<'_, char> {
+    match input.chars().next() {
+        Some(next) => Ok((&input[next.len_utf8()..], pos + next.len_utf8(), next)),
+        None => Err(ParseError::PrematureEndOfText(pos)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_literal() {
+        assert!(match_literal("(").parse("(abc", 0).is_ok());
+        assert!(match_literal("(").parse("abc", 0).is_err());
+    }
+
+    #[test]
+    fn test_pair_left_right() {
+        let joined = pair(match_literal("a"), match_literal("b"));
+        assert_eq!(
+            joined.parse("ab", 0).unwrap().2,
+            ("a".to_string(), "b".to_string())
+        );
+
+        assert_eq!(left(match_literal("a"), match_literal("b")).parse("ab", 0).unwrap().2, "a");
+        assert_eq!(right(match_literal("a"), match_literal("b")).parse("ab", 0).unwrap().2, "b");
+    }
+
+    #[test]
+    fn test_either() {
+        let parser = either(match_literal("a"), match_literal("b"));
+        assert!(parser.parse("a", 0).is_ok());
+        assert!(parser.parse("b", 0).is_ok());
+        assert!(parser.parse("c", 0).is_err());
+    }
+
+    fn letter_a(input: &str, pos: usize) -> ParseResult<'_, char> {
+        pred(any_char, |c: &char| *c == 'a').parse(input, pos)
+    }
+
+    #[test]
+    fn test_zero_or_more_and_one_or_more() {
+        assert_eq!(zero_or_more(letter_a).parse("aaab", 0).unwrap().2.len(), 3);
+        assert_eq!(zero_or_more(letter_a).parse("b", 0).unwrap().2.len(), 0);
+
+        assert_eq!(one_or_more(letter_a).parse("aaab", 0).unwrap().2.len(), 3);
+        assert!(one_or_more(letter_a).parse("b", 0).is_err());
+    }
+
+    #[test]
+    fn test_map_and_pred() {
+        let digit = pred(any_char, |c: &char| c.is_ascii_digit()).map(|c| c.to_digit(10).unwrap());
+        assert_eq!(digit.parse("5", 0).unwrap().2, 5);
+        assert!(digit.parse("x", 0).is_err());
+    }
+}
@@ -0,0 +1,493 @@
+//! A [`serde::Deserializer`] driven straight off the parsed [`Value`] tree, so a caller can
+//! write `let cfg: LoggingConfig = parser_combinators::from_str(text)?;` (re-exported at the
+//! crate root) instead of walking a `Vec<(String, Value)>` by hand.
+//!
+//! `from_str` parses with [`parse_option_string`], whose scalars are always [`Value::StringValue`],
+//! so numbers and booleans are recovered by parsing the string through the visitor method serde
+//! calls for the target field's type (`deserialize_u32`, `deserialize_bool`, ...). A
+//! [`Value::IntValue`]/[`Value::FloatValue`]/[`Value::BoolValue`] is handled too, by stringifying
+//! it the same way, in case a caller ever builds a `Deserializer` from a
+//! [`parse_option_string_typed`](crate::parse_option_string_typed) tree instead. `ObjectValue`
+//! drives struct/map access, `ListValue` drives sequence access, and fields absent from the input
+//! are left to serde's own `#[serde(default)]` handling.
+
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use thiserror::Error;
+
+use crate::{parse_option_string, ParseError, Value};
+
+/// Errors produced while deserializing a parsed option string into a user type.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("{0}")]
+    Message(String),
+    #[error("expected {expected}, found {found}")]
+    UnexpectedType {
+        expected: &'static str,
+        found: &'static str,
+    },
+}
+
+impl de::Error for Error {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+/// Deserialize `text` (a `key=value` option string) into `T`.
+pub fn from_str<'a, T>(text: &'a str) -> Result<T, Error>
+where
+    T: de::Deserialize<'a>,
+{
+    let (_, root) = parse_option_string(text)?;
+    T::deserialize(&mut Deserializer::from_value(Value::ObjectValue(root)))
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::StringValue(_) => "string",
+        Value::IntValue(_) => "int",
+        Value::FloatValue(_) => "float",
+        Value::BoolValue(_) => "bool",
+        Value::ListValue(_) => "list",
+        Value::ObjectValue(_) => "object",
+    }
+}
+
+/// Drives a [`serde::Deserializer`] over a single [`Value`] node.
+pub struct Deserializer {
+    value: Value,
+}
+
+impl Deserializer {
+    fn from_value(value: Value) -> Self {
+        Deserializer { value }
+    }
+
+    fn take_string(&mut self, expected: &'static str) -> Result<String, Error> {
+        match std::mem::replace(&mut self.value, Value::StringValue(String::new())) {
+            Value::StringValue(s) => Ok(s),
+            Value::IntValue(n) => Ok(n.to_string()),
+            Value::FloatValue(n) => Ok(n.to_string()),
+            Value::BoolValue(b) => Ok(b.to_string()),
+            other => Err(Error::UnexpectedType {
+                expected,
+                found: type_name(&other),
+            }),
+        }
+    }
+
+    fn parse_string<T>(&mut self, expected: &'static str) -> Result<T, Error>
+    where
+        T: std::str::FromStr,
+    {
+        let s = self.take_string(expected)?;
+        s.parse()
+            .map_err(|_| Error::Message(format!("'{s}' is not a valid {expected}")))
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($method:ident, $visit:ident, $name:literal) => {
+        fn $method<V>(self, visitor: V) -> Result<V::Value, Error>
+        where
+            V: Visitor<'de>,
+        {
+            visitor.$visit(self.parse_string($name)?)
+        }
+    };
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match std::mem::replace(&mut self.value, Value::StringValue(String::new())) {
+            Value::StringValue(s) => visitor.visit_string(s),
+            Value::IntValue(n) => visitor.visit_i64(n),
+            Value::FloatValue(n) => visitor.visit_f64(n),
+            Value::BoolValue(b) => visitor.visit_bool(b),
+            Value::ListValue(items) => visitor.visit_seq(SeqDeserializer::new(items)),
+            Value::ObjectValue(pairs) => visitor.visit_map(MapDeserializer::new(pairs)),
+        }
+    }
+
+    deserialize_parsed!(deserialize_bool, visit_bool, "bool");
+    deserialize_parsed!(deserialize_i8, visit_i8, "i8");
+    deserialize_parsed!(deserialize_i16, visit_i16, "i16");
+    deserialize_parsed!(deserialize_i32, visit_i32, "i32");
+    deserialize_parsed!(deserialize_i64, visit_i64, "i64");
+    deserialize_parsed!(deserialize_i128, visit_i128, "i128");
+    deserialize_parsed!(deserialize_u8, visit_u8, "u8");
+    deserialize_parsed!(deserialize_u16, visit_u16, "u16");
+    deserialize_parsed!(deserialize_u32, visit_u32, "u32");
+    deserialize_parsed!(deserialize_u64, visit_u64, "u64");
+    deserialize_parsed!(deserialize_u128, visit_u128, "u128");
+    deserialize_parsed!(deserialize_f32, visit_f32, "f32");
+    deserialize_parsed!(deserialize_f64, visit_f64, "f64");
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let s = self.take_string("char")?;
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => visitor.visit_char(c),
+            _ => Err(Error::Message(format!("'{s}' is not a single character"))),
+        }
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_string(self.take_string("string")?)
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_string(self.take_string("string")?)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_byte_buf(self.take_string("bytes")?.into_bytes())
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_byte_buf(self.take_string("bytes")?.into_bytes())
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        // The option-string format has no explicit "null"; a present key is always `Some`.
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match std::mem::replace(&mut self.value, Value::StringValue(String::new())) {
+            Value::ListValue(items) => visitor.visit_seq(SeqDeserializer::new(items)),
+            other => Err(Error::UnexpectedType {
+                expected: "list",
+                found: type_name(&other),
+            }),
+        }
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match std::mem::replace(&mut self.value, Value::StringValue(String::new())) {
+            Value::ObjectValue(pairs) => visitor.visit_map(MapDeserializer::new(pairs)),
+            other => Err(Error::UnexpectedType {
+                expected: "object",
+                found: type_name(&other),
+            }),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match std::mem::replace(&mut self.value, Value::StringValue(String::new())) {
+            Value::StringValue(variant) => visitor.visit_enum(variant.into_deserializer()),
+            Value::ObjectValue(pairs) if pairs.len() == 1 => {
+                visitor.visit_enum(EnumDeserializer { pair: pairs.into_iter().next().unwrap() })
+            }
+            other => Err(Error::UnexpectedType {
+                expected: "enum variant",
+                found: type_name(&other),
+            }),
+        }
+    }
+
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any(visitor)
+    }
+}
+
+struct SeqDeserializer {
+    iter: std::vec::IntoIter<Value>,
+}
+
+impl SeqDeserializer {
+    fn new(items: Vec<Value>) -> Self {
+        SeqDeserializer {
+            iter: items.into_iter(),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(value) => seed
+                .deserialize(&mut Deserializer::from_value(value))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+struct MapDeserializer {
+    iter: std::vec::IntoIter<(String, Value)>,
+    value: Option<Value>,
+}
+
+impl MapDeserializer {
+    fn new(pairs: Vec<(String, Value)>) -> Self {
+        MapDeserializer {
+            iter: pairs.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self
+            .value
+            .take()
+            .expect("next_value_seed called before next_key_seed");
+        seed.deserialize(&mut Deserializer::from_value(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// Drives an enum variant written as a one-entry object, e.g. `mode=(Custom='value')`. A variant
+/// written as a plain string (`mode='None'`) is handled in `deserialize_enum` without it.
+struct EnumDeserializer {
+    pair: (String, Value),
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = Error;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (variant, value) = self.pair;
+        let variant = seed.deserialize(IntoDeserializer::<Error>::into_deserializer(variant))?;
+        Ok((variant, VariantDeserializer { value }))
+    }
+}
+
+struct VariantDeserializer {
+    value: Value,
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut Deserializer::from_value(self.value))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_seq(&mut Deserializer::from_value(self.value), visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_map(&mut Deserializer::from_value(self.value), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct LoggingConfig {
+        #[serde(rename = "filePath")]
+        file_path: String,
+        #[serde(rename = "maxFiles")]
+        max_files: u32,
+        #[serde(rename = "logLevel", default)]
+        log_level: Option<String>,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Config {
+        logging: LoggingConfig,
+        modules: Vec<String>,
+        enabled: bool,
+    }
+
+    #[test]
+    fn test_from_str_struct() {
+        let text = "logging=(filePath='app.log';maxFiles='10');modules=['a';'b'];enabled='true'";
+        let config: Config = from_str(text).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                logging: LoggingConfig {
+                    file_path: "app.log".to_string(),
+                    max_files: 10,
+                    log_level: None,
+                },
+                modules: vec!["a".to_string(), "b".to_string()],
+                enabled: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_str_rejects_bad_number() {
+        #[derive(Deserialize, Debug)]
+        struct Port {
+            #[allow(dead_code)]
+            port: u16,
+        }
+
+        let result: Result<Port, Error> = from_str("port='not-a-number'");
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,382 @@
+//! C ABI bindings for D-PDU-API style hosts.
+//!
+//! This module exposes [`parse_option_string`](crate::parse_option_string) through the
+//! `pub_extern!` calling-convention macro (stdcall on x86, the platform default elsewhere) so
+//! that C/C++ hosts can parse `rdfPath`/`logging`/`modules` style option strings without linking
+//! against any Rust types directly. Every entry point catches Rust panics at the boundary and
+//! reports them as [`PDU_ERR_PANIC`] instead of unwinding into foreign code.
+
+// `pub_extern!` cannot mark its generated functions `unsafe` and still match the calling
+// convention a C host expects; the raw-pointer handling below is reviewed by hand instead.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+
+use crate::{parse_option_string, ParseError, Value};
+
+/// Error codes returned by the `pdu_*` entry points. `PDU_OK` is always zero; every other code
+/// is stable across releases so hosts can match on it without depending on message text.
+pub type PduErrorCode = i32;
+
+pub const PDU_OK: PduErrorCode = 0;
+pub const PDU_ERR_NULL_POINTER: PduErrorCode = 1;
+pub const PDU_ERR_INVALID_UTF8: PduErrorCode = 2;
+pub const PDU_ERR_IDENTIFIER_FIRST_CHARACTER_NOT_ALPHABETIC: PduErrorCode = 10;
+pub const PDU_ERR_PREMATURE_END_OF_TEXT: PduErrorCode = 11;
+pub const PDU_ERR_EXPECTED_LITERAL_NOT_FOUND: PduErrorCode = 12;
+pub const PDU_ERR_UNKNOWN_ESCAPED_SYMBOL: PduErrorCode = 13;
+pub const PDU_ERR_NO_VALUE_FOUND: PduErrorCode = 14;
+/// Only produced by hosts that parse with
+/// [`parse_option_string_recovering`](crate::parse_option_string_recovering); `pdu_parse` below
+/// uses the non-recovering [`parse_option_string`], which never returns this code.
+pub const PDU_ERR_RECOVERED_ENTRY: PduErrorCode = 15;
+pub const PDU_ERR_INVALID_UNICODE_ESCAPE: PduErrorCode = 16;
+pub const PDU_ERR_PANIC: PduErrorCode = 99;
+
+/// Node kind returned by [`pdu_node_kind`]. `PDU_KIND_INT`, `PDU_KIND_FLOAT` and `PDU_KIND_BOOL`
+/// are only produced by hosts that parse with [`parse_option_string_typed`](crate::parse_option_string_typed);
+/// `pdu_parse` below still uses the untyped [`parse_option_string`], so every scalar it returns is
+/// `PDU_KIND_STRING`.
+pub const PDU_KIND_STRING: i32 = 0;
+pub const PDU_KIND_LIST: i32 = 1;
+pub const PDU_KIND_OBJECT: i32 = 2;
+pub const PDU_KIND_INT: i32 = 3;
+pub const PDU_KIND_FLOAT: i32 = 4;
+pub const PDU_KIND_BOOL: i32 = 5;
+
+fn error_code(err: &ParseError) -> PduErrorCode {
+    match err {
+        ParseError::IdentifiersFirstCharacterNotAlphabetic(_) => {
+            PDU_ERR_IDENTIFIER_FIRST_CHARACTER_NOT_ALPHABETIC
+        }
+        ParseError::PrematureEndOfText(_) => PDU_ERR_PREMATURE_END_OF_TEXT,
+        ParseError::ExpectedLiteralNotFound(_, _) => PDU_ERR_EXPECTED_LITERAL_NOT_FOUND,
+        ParseError::UnknownEscapedSymbol(_, _) => PDU_ERR_UNKNOWN_ESCAPED_SYMBOL,
+        ParseError::InvalidUnicodeEscape(_) => PDU_ERR_INVALID_UNICODE_ESCAPE,
+        ParseError::NoValueFound(_) => PDU_ERR_NO_VALUE_FOUND,
+        ParseError::RecoveredEntry(_, _) => PDU_ERR_RECOVERED_ENTRY,
+    }
+}
+
+/// Owns a parsed option string for the lifetime of the handle. Every `*const c_char` handed back
+/// by an accessor is interned here, so it stays valid until the matching [`pdu_free`] call.
+pub struct PduHandle {
+    root: Vec<(String, Value)>,
+    strings: RefCell<Vec<CString>>,
+}
+
+impl PduHandle {
+    fn intern(&self, s: &str) -> *const c_char {
+        match CString::new(s) {
+            Ok(owned) => {
+                let ptr = owned.as_ptr();
+                self.strings.borrow_mut().push(owned);
+                ptr
+            }
+            Err(_) => ptr::null(),
+        }
+    }
+}
+
+/// An opaque handle over a node (string, list or object) inside a parsed [`PduHandle`] tree.
+/// Valid for as long as the owning handle has not been freed.
+#[repr(transparent)]
+pub struct PduNode(Value);
+
+fn as_node(value: &Value) -> *const PduNode {
+    (value as *const Value).cast::<PduNode>()
+}
+
+unsafe fn str_from_ptr<'a>(text: *const c_char) -> Result<&'a str, PduErrorCode> {
+    if text.is_null() {
+        return Err(PDU_ERR_NULL_POINTER);
+    }
+    CStr::from_ptr(text)
+        .to_str()
+        .map_err(|_| PDU_ERR_INVALID_UTF8)
+}
+
+unsafe fn pdu_parse_impl(text: *const c_char, out: *mut *mut PduHandle) -> PduErrorCode {
+    if out.is_null() {
+        return PDU_ERR_NULL_POINTER;
+    }
+    let text = match str_from_ptr(text) {
+        Ok(text) => text,
+        Err(code) => return code,
+    };
+
+    match parse_option_string(text) {
+        Ok((_, root)) => {
+            let handle = Box::new(PduHandle {
+                root,
+                strings: RefCell::new(Vec::new()),
+            });
+            *out = Box::into_raw(handle);
+            PDU_OK
+        }
+        Err(err) => error_code(&err),
+    }
+}
+
+pub_extern! {
+    /// Parse `text` as a D-PDU-API option string and hand back an opaque handle in `*out` on
+    /// success. Returns `PDU_OK` (zero) on success or one of the `PDU_ERR_*` codes otherwise;
+    /// `*out` is only written on success.
+    fn pdu_parse(text: *const c_char, out: *mut *mut PduHandle) -> PduErrorCode {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            pdu_parse_impl(text, out)
+        })) {
+            Ok(code) => code,
+            Err(_) => PDU_ERR_PANIC,
+        }
+    }
+}
+
+pub_extern! {
+    /// Free a handle previously returned by `pdu_parse`. Passing `NULL` is a no-op.
+    fn pdu_free(handle: *mut PduHandle) -> () {
+        let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if !handle.is_null() {
+                drop(unsafe { Box::from_raw(handle) });
+            }
+        }));
+    }
+}
+
+pub_extern! {
+    /// Number of top-level `key=value` entries in the parsed handle, or `0` if `handle` is NULL.
+    fn pdu_root_len(handle: *const PduHandle) -> usize {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return 0;
+            }
+            unsafe { &*handle }.root.len()
+        }))
+        .unwrap_or(0)
+    }
+}
+
+pub_extern! {
+    /// Key of the top-level entry at `index`, or `NULL` if out of bounds.
+    fn pdu_root_key(handle: *const PduHandle, index: usize) -> *const c_char {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return ptr::null();
+            }
+            let handle = unsafe { &*handle };
+            match handle.root.get(index) {
+                Some((key, _)) => handle.intern(key),
+                None => ptr::null(),
+            }
+        }))
+        .unwrap_or(ptr::null())
+    }
+}
+
+pub_extern! {
+    /// Value node of the top-level entry at `index`, or `NULL` if out of bounds.
+    fn pdu_root_value(handle: *const PduHandle, index: usize) -> *const PduNode {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return ptr::null();
+            }
+            match unsafe { &*handle }.root.get(index) {
+                Some((_, value)) => as_node(value),
+                None => ptr::null(),
+            }
+        }))
+        .unwrap_or(ptr::null())
+    }
+}
+
+pub_extern! {
+    /// Kind of `node`: one of `PDU_KIND_STRING`, `PDU_KIND_LIST` or `PDU_KIND_OBJECT`. Returns
+    /// `-1` if `node` is NULL.
+    fn pdu_node_kind(node: *const PduNode) -> i32 {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if node.is_null() {
+                return -1;
+            }
+            match unsafe { &*node }.0 {
+                Value::StringValue(_) => PDU_KIND_STRING,
+                Value::ListValue(_) => PDU_KIND_LIST,
+                Value::ObjectValue(_) => PDU_KIND_OBJECT,
+                Value::IntValue(_) => PDU_KIND_INT,
+                Value::FloatValue(_) => PDU_KIND_FLOAT,
+                Value::BoolValue(_) => PDU_KIND_BOOL,
+            }
+        }))
+        .unwrap_or(-1)
+    }
+}
+
+pub_extern! {
+    /// String contents of `node`, or `NULL` if `node` is not a string value, contains an interior
+    /// NUL character (e.g. from a `\0` escape), or is NULL.
+    fn pdu_node_string(handle: *const PduHandle, node: *const PduNode) -> *const c_char {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if handle.is_null() || node.is_null() {
+                return ptr::null();
+            }
+            match &unsafe { &*node }.0 {
+                Value::StringValue(s) => unsafe { &*handle }.intern(s),
+                _ => ptr::null(),
+            }
+        }))
+        .unwrap_or(ptr::null())
+    }
+}
+
+pub_extern! {
+    /// Number of elements if `node` is a list, otherwise `0`.
+    fn pdu_list_len(node: *const PduNode) -> usize {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if node.is_null() {
+                return 0;
+            }
+            match &unsafe { &*node }.0 {
+                Value::ListValue(items) => items.len(),
+                _ => 0,
+            }
+        }))
+        .unwrap_or(0)
+    }
+}
+
+pub_extern! {
+    /// Element `index` of a list `node`, or `NULL` if out of bounds or `node` is not a list.
+    fn pdu_list_get(node: *const PduNode, index: usize) -> *const PduNode {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if node.is_null() {
+                return ptr::null();
+            }
+            match &unsafe { &*node }.0 {
+                Value::ListValue(items) => items.get(index).map_or(ptr::null(), as_node),
+                _ => ptr::null(),
+            }
+        }))
+        .unwrap_or(ptr::null())
+    }
+}
+
+pub_extern! {
+    /// Number of `key=value` entries if `node` is an object, otherwise `0`.
+    fn pdu_object_len(node: *const PduNode) -> usize {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if node.is_null() {
+                return 0;
+            }
+            match &unsafe { &*node }.0 {
+                Value::ObjectValue(entries) => entries.len(),
+                _ => 0,
+            }
+        }))
+        .unwrap_or(0)
+    }
+}
+
+pub_extern! {
+    /// Key of the object entry at `index`, or `NULL` if out of bounds or `node` is not an object.
+    fn pdu_object_key(handle: *const PduHandle, node: *const PduNode, index: usize) -> *const c_char {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if handle.is_null() || node.is_null() {
+                return ptr::null();
+            }
+            match &unsafe { &*node }.0 {
+                Value::ObjectValue(entries) => entries
+                    .get(index)
+                    .map_or(ptr::null(), |(key, _)| unsafe { &*handle }.intern(key)),
+                _ => ptr::null(),
+            }
+        }))
+        .unwrap_or(ptr::null())
+    }
+}
+
+pub_extern! {
+    /// Value node of the object entry at `index`, or `NULL` if out of bounds or `node` is not an
+    /// object.
+    fn pdu_object_value(node: *const PduNode, index: usize) -> *const PduNode {
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            if node.is_null() {
+                return ptr::null();
+            }
+            match &unsafe { &*node }.0 {
+                Value::ObjectValue(entries) => {
+                    entries.get(index).map_or(ptr::null(), |(_, value)| as_node(value))
+                }
+                _ => ptr::null(),
+            }
+        }))
+        .unwrap_or(ptr::null())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    unsafe fn parse(text: &str) -> *mut PduHandle {
+        let text = CString::new(text).unwrap();
+        let mut handle: *mut PduHandle = ptr::null_mut();
+        assert_eq!(pdu_parse(text.as_ptr(), &mut handle), PDU_OK);
+        assert!(!handle.is_null());
+        handle
+    }
+
+    #[test]
+    fn test_pdu_parse_roundtrip() {
+        unsafe {
+            let handle = parse("logging=(filePath='test.path';maxFiles='10')");
+            assert_eq!(pdu_root_len(handle), 1);
+
+            let key = CStr::from_ptr(pdu_root_key(handle, 0)).to_str().unwrap();
+            assert_eq!(key, "logging");
+
+            let logging = pdu_root_value(handle, 0);
+            assert_eq!(pdu_node_kind(logging), PDU_KIND_OBJECT);
+            assert_eq!(pdu_object_len(logging), 2);
+
+            let file_path_key = CStr::from_ptr(pdu_object_key(handle, logging, 0))
+                .to_str()
+                .unwrap();
+            assert_eq!(file_path_key, "filePath");
+
+            let file_path_value = pdu_object_value(logging, 0);
+            assert_eq!(pdu_node_kind(file_path_value), PDU_KIND_STRING);
+            let file_path = CStr::from_ptr(pdu_node_string(handle, file_path_value))
+                .to_str()
+                .unwrap();
+            assert_eq!(file_path, "test.path");
+
+            pdu_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_pdu_parse_list_and_errors() {
+        unsafe {
+            let handle = parse("modules=[(name='a');(name='b')]");
+            let modules = pdu_root_value(handle, 0);
+            assert_eq!(pdu_node_kind(modules), PDU_KIND_LIST);
+            assert_eq!(pdu_list_len(modules), 2);
+
+            let first = pdu_list_get(modules, 0);
+            assert_eq!(pdu_node_kind(first), PDU_KIND_OBJECT);
+
+            assert!(pdu_list_get(modules, 99).is_null());
+            pdu_free(handle);
+        }
+    }
+
+    #[test]
+    fn test_pdu_parse_reports_null_and_invalid_input() {
+        let mut handle: *mut PduHandle = ptr::null_mut();
+        assert_eq!(pdu_parse(ptr::null(), &mut handle), PDU_ERR_NULL_POINTER);
+
+        let bad = CString::new("key=").unwrap();
+        assert_ne!(pdu_parse(bad.as_ptr(), &mut handle), PDU_OK);
+    }
+}
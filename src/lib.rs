@@ -1,15 +1,79 @@
 // Source code for the blogpost: https://bodil.lol/parser-combinators/
 
-use std::str::CharIndices;
 use thiserror::Error;
 
+use combinators::{
+    any_char, left, map, match_literal, one_or_more, pair, pred, right, zero_or_more, ParseResult,
+    Parser,
+};
+
+#[macro_use]
+mod thing;
+pub mod combinators;
+pub mod de;
+pub mod ffi;
+pub mod render;
+
+/// Re-exported so `serde`-based callers can write `parser_combinators::from_str(text)` instead of
+/// reaching into the [`de`] module by hand.
+pub use de::{from_str, Error};
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     StringValue(String),
+    IntValue(i64),
+    FloatValue(f64),
+    BoolValue(bool),
     ListValue(Vec<Value>),
     ObjectValue(Vec<(String, Value)>),
 }
 
+impl Value {
+    /// Reads this value as an `i64`, parsing a [`Value::StringValue`]'s contents if needed so
+    /// callers don't care whether the source option string quoted the number or not.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::IntValue(n) => Some(*n),
+            Value::FloatValue(n) if n.fract() == 0.0 => Some(*n as i64),
+            Value::StringValue(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Reads this value as a `bool`, accepting `'true'`/`'false'` in a [`Value::StringValue`] as
+    /// well as a native [`Value::BoolValue`].
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::BoolValue(b) => Some(*b),
+            Value::StringValue(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Borrows this value as a string slice. Only [`Value::StringValue`] has one to borrow;
+    /// numeric and boolean values were never quoted text, so this returns `None` for them.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::StringValue(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// A byte-offset range into the original input, used to mark the text a recovering parse skipped
+/// over after a malformed entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.start, self.end)
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum ParseError {
     #[error("Identifiers first character is not alphabetic! Position `{0}`!")]
@@ -20,54 +84,85 @@ pub enum ParseError {
     ExpectedLiteralNotFound(usize, String),
     #[error("Unknown excaped symbol '{1}'! Position `{0}`!")]
     UnknownEscapedSymbol(usize, char),
+    #[error("Invalid unicode escape! Position `{0}`!")]
+    InvalidUnicodeEscape(usize),
     #[error("No value found! Position `{0}`!")]
     NoValueFound(usize),
+    #[error("Skipped malformed entry at {0}: {1}")]
+    RecoveredEntry(Span, Box<ParseError>),
 }
 
-type ParseResult<'a, Output> = Result<(&'a str, usize, Output), ParseError>;
-
-fn identifier(text: &str, position: usize) -> ParseResult<String> {
-    let mut chars = text.char_indices();
-
-    let first_ident_char = match chars.next() {
-        Some(next) => {
-            if !next.1.is_alphabetic() {
-                return Err(ParseError::IdentifiersFirstCharacterNotAlphabetic(0));
-            }
-            next.1
+impl ParseError {
+    /// The byte offset into the original input this error points at, for line/column reporting.
+    pub fn position(&self) -> usize {
+        match self {
+            ParseError::IdentifiersFirstCharacterNotAlphabetic(pos) => *pos,
+            ParseError::PrematureEndOfText(pos) => *pos,
+            ParseError::ExpectedLiteralNotFound(pos, _) => *pos,
+            ParseError::UnknownEscapedSymbol(pos, _) => *pos,
+            ParseError::InvalidUnicodeEscape(pos) => *pos,
+            ParseError::NoValueFound(pos) => *pos,
+            ParseError::RecoveredEntry(span, _) => span.start,
         }
-        None => return Err(ParseError::PrematureEndOfText(position)),
-    };
+    }
+}
 
-    let last_non_ident_char =
-        chars.find(|item| !(item.1.is_alphanumeric() || item.1 == '-' || item.1 == '_'));
+/// Computes the 1-indexed `(line, column)` of a byte offset into `text`, counting `\n` to move to
+/// the next line the way a text editor would.
+pub fn line_col(text: &str, byte_pos: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut col = 1;
+    for c in text[..byte_pos.min(text.len())].chars() {
+        if c == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    (line, col)
+}
 
-    let ident_string = first_ident_char.to_string();
+/// Renders `err` as `line:col: message`, followed by the offending line of `text` with a `^`
+/// caret under the column the error points at (e.g. ``3:17: Expected literal ')' not found! Position `N`!``, where `N` is the byte offset).
+pub fn render_parse_error(text: &str, err: &ParseError) -> String {
+    let (line, col) = line_col(text, err.position());
+    let offending_line = text.lines().nth(line - 1).unwrap_or("");
+    format!("{line}:{col}: {err}\n{offending_line}\n{:>width$}", "^", width = col)
+}
 
-    match last_non_ident_char {
-        Some(last) => Ok((
-            &text[last.0..],
-            position + last.0,
-            text[..last.0].to_string(),
-        )),
-        None => Ok((
-            &text[first_ident_char.len_utf8()..],
-            position + first_ident_char.len_utf8(),
-            ident_string,
-        )),
+// The first character gets its own dedicated error (`IdentifiersFirstCharacterNotAlphabetic`)
+// rather than the generic `pred` failure, so it is matched by hand instead of via `pred(any_char, ..)`.
+fn identifier_first_char(input: &str, pos: usize) -> ParseResult<'_, char> {
+    match any_char(input, pos) {
+        Ok((next_input, next_pos, c)) if c.is_alphabetic() => Ok((next_input, next_pos, c)),
+        Ok(_) => Err(ParseError::IdentifiersFirstCharacterNotAlphabetic(pos)),
+        Err(err) => Err(err),
     }
 }
 
-fn skip_white_space(text: &str, position: usize) -> ParseResult<()> {
-    let first_no_whitespace = text.char_indices().find(|item| !item.1.is_whitespace());
+fn identifier(text: &str, position: usize) -> ParseResult<'_, String> {
+    let rest_chars = zero_or_more(pred(any_char, |c: &char| {
+        c.is_alphanumeric() || *c == '-' || *c == '_'
+    }));
+
+    map(
+        pair(identifier_first_char, rest_chars),
+        |(first, rest): (char, Vec<char>)| {
+            let mut ident = String::with_capacity(1 + rest.len());
+            ident.push(first);
+            ident.extend(rest);
+            ident
+        },
+    )
+    .parse(text, position)
+}
 
-    match first_no_whitespace {
-        Some(item) => Ok((&text[item.0..], position + item.0, ())),
-        None => Ok((&text[text.len()..], position + text.len(), ())),
-    }
+fn skip_white_space(text: &str, position: usize) -> ParseResult<'_, ()> {
+    map(zero_or_more(pred(any_char, |c: &char| c.is_whitespace())), |_| ()).parse(text, position)
 }
 
-fn literal<'a>(text: &'a str, position: usize, expected: &str) -> ParseResult<'a, String> {
+pub(crate) fn literal<'a>(text: &'a str, position: usize, expected: &str) -> ParseResult<'a, String> {
     match text.starts_with(expected) {
         true => Ok((
             &text[expected.len()..],
@@ -81,62 +176,165 @@ fn literal<'a>(text: &'a str, position: usize, expected: &str) -> ParseResult<'a
     }
 }
 
-fn single_quoted_string(text: &str, position: usize) -> ParseResult<String> {
-    let start_quote_output = literal(text, position, "\'")?;
-    let (text, position, _) = start_quote_output;
-
-    let mut content = String::new();
+/// Reads the hex digits of a `\uXXXX` or `\u{XXXX}` escape (the `\u` itself already consumed) and
+/// decodes them into the `char` they name.
+fn unicode_escape_char(input: &str, pos: usize) -> ParseResult<'_, char> {
+    let (mut text, mut position, braced) = match match_literal("{").parse(input, pos) {
+        Ok((text, position, _)) => (text, position, true),
+        Err(_) => (input, pos, false),
+    };
 
-    let mut char_indicies = text.char_indices();
+    let mut hex = String::new();
+    if braced {
+        loop {
+            match any_char(text, position) {
+                Ok((next_text, next_position, '}')) => {
+                    text = next_text;
+                    position = next_position;
+                    break;
+                }
+                Ok((next_text, next_position, c)) if c.is_ascii_hexdigit() && hex.len() < 6 => {
+                    hex.push(c);
+                    text = next_text;
+                    position = next_position;
+                }
+                _ => return Err(ParseError::InvalidUnicodeEscape(pos)),
+            }
+        }
+    } else {
+        for _ in 0..4 {
+            match any_char(text, position) {
+                Ok((next_text, next_position, c)) if c.is_ascii_hexdigit() => {
+                    hex.push(c);
+                    text = next_text;
+                    position = next_position;
+                }
+                _ => return Err(ParseError::InvalidUnicodeEscape(pos)),
+            }
+        }
+    }
 
-    let mut err_position = position;
-    let last_char = loop {
-        let next_char = char_indicies.next();
-        match next_char {
-            Some(next) => match next.1 {
-                '\'' => break next,
-                '\\' => content.push(escaped_char(&mut char_indicies, position + next.0)?),
+    u32::from_str_radix(&hex, 16)
+        .ok()
+        .and_then(char::from_u32)
+        .map(|c| (text, position, c))
+        .ok_or(ParseError::InvalidUnicodeEscape(pos))
+}
 
-                _ => content.push(next.1),
-            },
-            None => return Err(ParseError::PrematureEndOfText(err_position)),
-        }
-        err_position = position + next_char.unwrap().0;
-    };
+fn escaped_char(input: &str, pos: usize) -> ParseResult<'_, char> {
+    let (next_input, next_pos, c) = any_char(input, pos)?;
+    match c {
+        '\'' | '"' | '\\' => Ok((next_input, next_pos, c)),
+        'n' => Ok((next_input, next_pos, '\n')),
+        't' => Ok((next_input, next_pos, '\t')),
+        'r' => Ok((next_input, next_pos, '\r')),
+        '0' => Ok((next_input, next_pos, '\0')),
+        'u' => unicode_escape_char(next_input, next_pos),
+        _ => Err(ParseError::UnknownEscapedSymbol(pos, c)),
+    }
+}
 
-    Ok((&text[(last_char.0 + 1)..], position + last_char.0, content))
+// Not expressed as `either(right(match_literal("\\"), escaped_char), pred(...))`: `either` retries
+// its second branch from the *original* position on any failure, so an unsupported escape (e.g.
+// `\x`) would make it fall through to `pred(any_char, |c| *c != '\'')`, which happily reconsumes
+// the backslash as a plain character instead of propagating `UnknownEscapedSymbol`. Dispatching
+// on the backslash by hand keeps that error real.
+fn single_quoted_string_char(input: &str, pos: usize) -> ParseResult<'_, char> {
+    match match_literal("\\").parse(input, pos) {
+        Ok((rest, next_pos, _)) => escaped_char(rest, next_pos),
+        Err(_) => pred(any_char, |c: &char| *c != '\'').parse(input, pos),
+    }
 }
 
-fn escaped_char(char_indicies: &mut CharIndices, position: usize) -> Result<char, ParseError> {
-    match char_indicies.next() {
-        Some(next_after_escape) => match next_after_escape.1 {
-            '\'' | '\\' => Ok(next_after_escape.1),
-            _ => Err(ParseError::UnknownEscapedSymbol(
-                position,
-                next_after_escape.1,
-            )),
-        },
-        None => Err(ParseError::PrematureEndOfText(position)),
+// Same hand-dispatch as `single_quoted_string_char`, for the same reason: falling through to
+// `either`'s second branch would reconsume a malformed escape's backslash as a plain character.
+fn double_quoted_string_char(input: &str, pos: usize) -> ParseResult<'_, char> {
+    match match_literal("\\").parse(input, pos) {
+        Ok((rest, next_pos, _)) => escaped_char(rest, next_pos),
+        Err(_) => pred(any_char, |c: &char| *c != '"').parse(input, pos),
     }
 }
 
-fn key_value_pair(text: &str, position: usize) -> ParseResult<(String, Value)> {
-    let key = identifier(text, position)?;
+// Not expressed via `zero_or_more(single_quoted_string_char)`: `zero_or_more` stops as soon as
+// its inner parser errors, for *any* reason, and quietly returns what it collected so far rather
+// than propagating the error. That would swallow a genuine `UnknownEscapedSymbol` from
+// `single_quoted_string_char` the same way `either` did above, so the loop is written by hand
+// here, the same way `key_value_pairs`/`object`/`list` already do where a combinator can't tell
+// "done" from "failed".
+fn single_quoted_string(text: &str, position: usize) -> ParseResult<'_, String> {
+    let (mut text, mut position, _) = match_literal("'").parse(text, position)?;
+    let mut content = String::new();
 
-    let (text, position, _) = key;
-    let (text, position, _) = skip_white_space(text, position)?;
+    loop {
+        if let Ok((text, position, _)) = match_literal("'").parse(text, position) {
+            return Ok((text, position, content));
+        }
+        if text.is_empty() {
+            return Err(ParseError::PrematureEndOfText(position));
+        }
 
-    let equals = literal(text, position, "=")?;
+        let (next_text, next_position, c) = single_quoted_string_char(text, position)?;
+        text = next_text;
+        position = next_position;
+        content.push(c);
+    }
+}
 
-    let (text, position, _) = equals;
-    let (text, position, _) = skip_white_space(text, position)?;
+/// Like [`single_quoted_string`], but for `"..."` literals — the convention authors writing
+/// Windows file paths naturally reach for. The same escapes apply as inside `'...'`, so a `\`
+/// separator is still written `\\`.
+fn double_quoted_string(text: &str, position: usize) -> ParseResult<'_, String> {
+    let (mut text, mut position, _) = match_literal("\"").parse(text, position)?;
+    let mut content = String::new();
+
+    loop {
+        if let Ok((text, position, _)) = match_literal("\"").parse(text, position) {
+            return Ok((text, position, content));
+        }
+        if text.is_empty() {
+            return Err(ParseError::PrematureEndOfText(position));
+        }
+
+        let (next_text, next_position, c) = double_quoted_string_char(text, position)?;
+        text = next_text;
+        position = next_position;
+        content.push(c);
+    }
+}
 
-    let value = value(text, position)?;
+/// Parses a quoted string in either convention, dispatching on the opening quote character. Not
+/// expressed as `either(single_quoted_string, double_quoted_string)`: `either` retries its second
+/// branch from the *original* position on any failure, so a malformed `'...'` literal (say, a bad
+/// escape) would fall through to `double_quoted_string`, which immediately fails to match `"` and
+/// reports that uninteresting error instead of the real one. Dispatching on the opening quote by
+/// hand keeps it real, the same reasoning [`single_quoted_string_char`] already relies on.
+fn quoted_string(text: &str, position: usize) -> ParseResult<'_, String> {
+    if text.starts_with('"') {
+        double_quoted_string(text, position)
+    } else {
+        single_quoted_string(text, position)
+    }
+}
 
-    Ok((value.0, value.1, (key.2, value.2)))
+fn key_value_pair_with<'a>(
+    text: &'a str,
+    position: usize,
+    parse_value: impl Fn(&'a str, usize) -> ParseResult<'a, Value>,
+) -> ParseResult<'a, (String, Value)> {
+    let (text, position, key) = left(
+        identifier,
+        right(skip_white_space, right(match_literal("="), skip_white_space)),
+    )
+    .parse(text, position)?;
+    let (text, position, value) = parse_value(text, position)?;
+    Ok((text, position, (key, value)))
 }
 
-fn key_value_pairs(text: &str, position: usize) -> ParseResult<Vec<(String, Value)>> {
+fn key_value_pairs_with<'a>(
+    text: &'a str,
+    position: usize,
+    parse_value: impl Fn(&'a str, usize) -> ParseResult<'a, Value> + Copy,
+) -> ParseResult<'a, Vec<(String, Value)>> {
     let (text, position, _) = skip_white_space(text, position)?;
     if text.is_empty() {
         return Ok((text, position, vec![]));
@@ -144,7 +342,7 @@ fn key_value_pairs(text: &str, position: usize) -> ParseResult<Vec<(String, Valu
 
     let mut key_value_pairs = vec![];
 
-    let first_key_value_pair = key_value_pair(text, position)?;
+    let first_key_value_pair = key_value_pair_with(text, position, parse_value)?;
     let mut text = first_key_value_pair.0;
     let mut position = first_key_value_pair.1;
     key_value_pairs.push(first_key_value_pair.2);
@@ -154,7 +352,7 @@ fn key_value_pairs(text: &str, position: usize) -> ParseResult<Vec<(String, Valu
         let previous_position = position;
 
         (text, position, _) = skip_white_space(text, position)?;
-        let semicolon = match literal(text, position, ";") {
+        let semicolon = match match_literal(";").parse(text, position) {
             Ok(output) => output,
             Err(_) => return Ok((previous_text, previous_position, key_value_pairs)),
         };
@@ -162,7 +360,7 @@ fn key_value_pairs(text: &str, position: usize) -> ParseResult<Vec<(String, Valu
 
         (text, position, _) = skip_white_space(text, position)?;
 
-        let a_key_value_pair_result = key_value_pair(text, position);
+        let a_key_value_pair_result = key_value_pair_with(text, position, parse_value);
         if a_key_value_pair_result.is_err() {
             return Ok((semicolon.0, semicolon.1, key_value_pairs));
         }
@@ -173,38 +371,41 @@ fn key_value_pairs(text: &str, position: usize) -> ParseResult<Vec<(String, Valu
     }
 }
 
-fn object(text: &str, position: usize) -> ParseResult<Vec<(String, Value)>> {
-    let bracket = literal(text, position, "(")?;
-    let (text, position, _) = bracket;
-
+fn object_with<'a>(
+    text: &'a str,
+    position: usize,
+    parse_value: impl Fn(&'a str, usize) -> ParseResult<'a, Value> + Copy,
+) -> ParseResult<'a, Vec<(String, Value)>> {
+    let (text, position, _) = match_literal("(").parse(text, position)?;
     let (text, position, _) = skip_white_space(text, position)?;
 
-    let content_result = key_value_pairs(text, position);
+    let content_result = key_value_pairs_with(text, position, parse_value);
     if content_result.is_err() {
-        let (text, position, _) = literal(text, position, ")")?;
+        let (text, position, _) = match_literal(")").parse(text, position)?;
         return Ok((text, position, vec![]));
     }
     let content = content_result.unwrap();
     let (text, position, _) = content;
 
     let (text, position, _) = skip_white_space(text, position)?;
-
-    let (text, position, _) = literal(text, position, ")")?;
+    let (text, position, _) = match_literal(")").parse(text, position)?;
 
     Ok((text, position, content.2))
 }
 
-fn list(text: &str, position: usize) -> ParseResult<Vec<Value>> {
-    let (text, position, _) = literal(text, position, "[")?;
-
+fn list_with<'a>(
+    text: &'a str,
+    position: usize,
+    parse_value: impl Fn(&'a str, usize) -> ParseResult<'a, Value> + Copy,
+) -> ParseResult<'a, Vec<Value>> {
+    let (text, position, _) = match_literal("[").parse(text, position)?;
     let (text, position, _) = skip_white_space(text, position)?;
 
     let mut values = vec![];
 
-    let first_value_result = value(text, position);
+    let first_value_result = parse_value(text, position);
     if first_value_result.is_err() {
-        let (text, position, _) = literal(text, position, "]")?;
-
+        let (text, position, _) = match_literal("]").parse(text, position)?;
         return Ok((text, position, vec![]));
     }
     let first_value = first_value_result.unwrap();
@@ -219,7 +420,7 @@ fn list(text: &str, position: usize) -> ParseResult<Vec<Value>> {
 
         (text, position, _) = skip_white_space(text, position)?;
 
-        let semicolon = match literal(text, position, ";") {
+        let semicolon = match match_literal(";").parse(text, position) {
             Ok(output) => output,
             Err(_) => {
                 text = previous_text;
@@ -232,35 +433,372 @@ fn list(text: &str, position: usize) -> ParseResult<Vec<Value>> {
 
         (text, position, _) = skip_white_space(text, position)?;
 
-        let a_value = value(text, position)?;
+        let a_value = parse_value(text, position)?;
         (text, position, _) = a_value;
         values.push(a_value.2);
     }
 
     let (text, position, _) = skip_white_space(text, position)?;
-
-    let (text, position, _) = literal(text, position, "]")?;
+    let (text, position, _) = match_literal("]").parse(text, position)?;
     Ok((text, position, values))
 }
 
-fn value(text: &str, position: usize) -> ParseResult<Value> {
-    if let Ok(value) = single_quoted_string(text, position) {
-        return Ok((value.0, value.1, Value::StringValue(value.2)));
+fn key_value_pairs(text: &str, position: usize) -> ParseResult<'_, Vec<(String, Value)>> {
+    key_value_pairs_with(text, position, value)
+}
+
+fn object(text: &str, position: usize) -> ParseResult<'_, Vec<(String, Value)>> {
+    object_with(text, position, value)
+}
+
+fn list(text: &str, position: usize) -> ParseResult<'_, Vec<Value>> {
+    list_with(text, position, value)
+}
+
+// Not expressed via `either`: `either` only ever surfaces its *last* branch's error on total
+// failure, so a malformed quoted string (e.g. `'unterminated`) would be reported as `object`'s
+// unrelated "expected '(' not found" instead. Dispatching by hand, the same reasoning
+// `quoted_string`/`single_quoted_string_char` already rely on, and falling back to the original
+// `NoValueFound` when none of the three alternatives match keeps the failure generic rather than
+// actively misleading — no branch's specific error is propagated, but at least the wrong one isn't
+// either.
+fn value(text: &str, position: usize) -> ParseResult<'_, Value> {
+    if let Ok((text, position, s)) = quoted_string(text, position) {
+        return Ok((text, position, Value::StringValue(s)));
+    }
+    if let Ok((text, position, items)) = list(text, position) {
+        return Ok((text, position, Value::ListValue(items)));
     }
+    if let Ok((text, position, pairs)) = object(text, position) {
+        return Ok((text, position, Value::ObjectValue(pairs)));
+    }
+    Err(ParseError::NoValueFound(position))
+}
+
+/// The top-level `key=value` pairs parsed from an option string, alongside any trailing, unparsed
+/// text. Named so `parse_option_string`/`parse_option_string_typed` don't each spell out the same
+/// nested `Result<(&str, Vec<(String, Value)>), _>` type.
+pub type ParsedOptionString<'a> = Result<(&'a str, Vec<(String, Value)>), ParseError>;
+
+pub fn parse_option_string(text: &str) -> ParsedOptionString<'_> {
+    key_value_pairs(text, 0).map(|output| (output.0, output.2))
+}
+
+// `parse_option_string` treats every scalar as a quoted `Value::StringValue`, matching the
+// D-PDU-API option strings it was written for. `parse_option_string_typed` below is a separate
+// entry point for dialects that also write unquoted scalars (`maxFiles=10`, `Udp13401=true`): it
+// runs the same `key_value_pairs_with`/`object_with`/`list_with` grammar, passing `value_typed`
+// instead of `value`, so `parse_option_string`'s own behaviour is unchanged.
+
+fn is_bare_word_char(c: &char) -> bool {
+    c.is_alphanumeric() || *c == '-' || *c == '_' || *c == '.' || *c == '+'
+}
+
+fn bare_word(text: &str, position: usize) -> ParseResult<'_, String> {
+    map(one_or_more(pred(any_char, is_bare_word_char)), |chars: Vec<char>| {
+        chars.into_iter().collect()
+    })
+    .parse(text, position)
+}
+
+/// Parses an unquoted scalar, preferring `true`/`false` as a [`Value::BoolValue`], then an
+/// integer, then a float, and falling back to a plain [`Value::StringValue`].
+fn typed_scalar(text: &str, position: usize) -> ParseResult<'_, Value> {
+    map(bare_word, |word: String| match word.as_str() {
+        "true" => Value::BoolValue(true),
+        "false" => Value::BoolValue(false),
+        _ => word
+            .parse::<i64>()
+            .map(Value::IntValue)
+            .or_else(|_| word.parse::<f64>().map(Value::FloatValue))
+            .unwrap_or(Value::StringValue(word)),
+    })
+    .parse(text, position)
+}
 
-    if let Ok(value) = list(text, position) {
-        return Ok((value.0, value.1, Value::ListValue(value.2)));
+fn key_value_pairs_typed(text: &str, position: usize) -> ParseResult<'_, Vec<(String, Value)>> {
+    key_value_pairs_with(text, position, value_typed)
+}
+
+fn object_typed(text: &str, position: usize) -> ParseResult<'_, Vec<(String, Value)>> {
+    object_with(text, position, value_typed)
+}
+
+fn list_typed(text: &str, position: usize) -> ParseResult<'_, Vec<Value>> {
+    list_with(text, position, value_typed)
+}
+
+// Same hand dispatch as `value`/`value_recovering`, with the bare-word `typed_scalar` tried last.
+// When nothing matches at all, the result is `NoValueFound` at the value's position.
+fn value_typed(text: &str, position: usize) -> ParseResult<'_, Value> {
+    if let Ok((text, position, s)) = quoted_string(text, position) {
+        return Ok((text, position, Value::StringValue(s)));
+    }
+    if let Ok((text, position, items)) = list_typed(text, position) {
+        return Ok((text, position, Value::ListValue(items)));
+    }
+    if let Ok((text, position, pairs)) = object_typed(text, position) {
+        return Ok((text, position, Value::ObjectValue(pairs)));
+    }
+    if let Ok((text, position, v)) = typed_scalar(text, position) {
+        return Ok((text, position, v));
     }
+    Err(ParseError::NoValueFound(position))
+}
 
-    if let Ok(value) = object(text, position) {
-        return Ok((value.0, value.1, Value::ObjectValue(value.2)));
+/// Like [`parse_option_string`], but scalars may also be written unquoted (`maxFiles=10`,
+/// `Udp13401=true`), in which case they come back as [`Value::IntValue`], [`Value::FloatValue`]
+/// or [`Value::BoolValue`] instead of [`Value::StringValue`]. Quoted scalars still parse exactly
+/// as they do through `parse_option_string`.
+pub fn parse_option_string_typed(text: &str) -> ParsedOptionString<'_> {
+    key_value_pairs_typed(text, 0).map(|output| (output.0, output.2))
+}
+
+// `parse_option_string`/`parse_option_string_typed` abort on the first malformed entry, which is
+// fine for validating a single option string but useless for a config tool that wants to report
+// every mistake in a file in one pass. `parse_option_string_recovering` below skips a malformed
+// `key_value_pair`/`value` up to the next `;` or closing bracket instead of failing outright,
+// recording what it skipped as a `ParseError::RecoveredEntry` rather than losing the entry
+// silently; nested objects/lists recover the same way, so one malformed module deep inside
+// `modules=[...]` doesn't take the rest of the list down with it.
+
+/// Whether `text` starts with something that reads like the start of a fresh `key_value_pair`
+/// (an [`identifier`] followed by `=`), the same shape [`skip_to_boundary`] looks for once it's
+/// past the first skipped character, so a resync doesn't have to wait for a `;`/`)`/`]` that may
+/// never come before the next legitimate entry.
+fn looks_like_entry_start(text: &str) -> bool {
+    left(identifier, right(skip_white_space, match_literal("=")))
+        .parse(text, 0)
+        .is_ok()
+}
+
+/// Advances from `position` to the next `;`, `)`, `]`, the start of what looks like a fresh
+/// `key_value_pair`, or the end of `text`, without consuming it, and reports the skipped range as
+/// a [`Span`]. Always skips at least one character, so a caller that's already sitting on a
+/// boundary character still makes progress — this also means a stray closing bracket right next
+/// to a legitimate entry doesn't swallow that entry along with it. A `;`/`)`/`]` inside a `'...'`
+/// or `"..."` literal (tracked the same way [`single_quoted_string_char`]/[`double_quoted_string_char`]
+/// would) doesn't count as a boundary, so skipping doesn't resync in the middle of a string
+/// value's text.
+fn skip_to_boundary(text: &str, position: usize) -> (&str, usize, Span) {
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '-' || c == '_';
+
+    let mut chars = text.char_indices();
+    let (mut in_single_quotes, mut in_double_quotes, mut prev_is_identifier_char) =
+        match chars.next() {
+            Some((_, c)) => (c == '\'', c == '"', is_identifier_char(c)),
+            None => (false, false, false),
+        };
+
+    let mut escaped = false;
+    let mut end_offset = text.len();
+    for (i, c) in chars {
+        let in_string = in_single_quotes || in_double_quotes;
+        if escaped {
+            escaped = false;
+            prev_is_identifier_char = is_identifier_char(c);
+            continue;
+        }
+        match c {
+            '\\' if in_string => escaped = true,
+            '\'' if !in_double_quotes => in_single_quotes = !in_single_quotes,
+            '"' if !in_single_quotes => in_double_quotes = !in_double_quotes,
+            ';' | ')' | ']' if !in_string => {
+                end_offset = i;
+                break;
+            }
+            // Only probe for a fresh entry at a word boundary: checking mid-identifier (e.g. the
+            // "ype=" tail of a malformed "type=...") would false-positive on the entry being
+            // skipped rather than finding the next real one.
+            _ if !in_string && !prev_is_identifier_char && looks_like_entry_start(&text[i..]) => {
+                end_offset = i;
+                break;
+            }
+            _ => {}
+        }
+        prev_is_identifier_char = is_identifier_char(c);
     }
 
+    (
+        &text[end_offset..],
+        position + end_offset,
+        Span {
+            start: position,
+            end: position + end_offset,
+        },
+    )
+}
+
+// Same reasoning as `value`'s hand dispatch above: falling through to `object_recovering`'s own
+// error on total failure would surface its uninteresting "expected '(' not found" (e.g. for the
+// empty value in `k=)`) instead of a real `NoValueFound`.
+fn value_recovering(text: &str, position: usize) -> ParseResult<'_, (Value, Vec<ParseError>)> {
+    if let Ok((text, position, s)) = quoted_string(text, position) {
+        return Ok((text, position, (Value::StringValue(s), vec![])));
+    }
+    if let Ok((text, position, (items, errors))) = list_recovering(text, position) {
+        return Ok((text, position, (Value::ListValue(items), errors)));
+    }
+    if let Ok((text, position, (pairs, errors))) = object_recovering(text, position) {
+        return Ok((text, position, (Value::ObjectValue(pairs), errors)));
+    }
     Err(ParseError::NoValueFound(position))
 }
 
-pub fn parse_option_string(text: &str) -> Result<(&str, Vec<(String, Value)>), ParseError> {
-    key_value_pairs(text, 0).map(|output| (output.0, output.2))
+fn key_value_pair_recovering<'a>(
+    text: &'a str,
+    position: usize,
+) -> ParseResult<'a, (String, Value, Vec<ParseError>)> {
+    let (text, position, key) = left(
+        identifier,
+        right(skip_white_space, right(match_literal("="), skip_white_space)),
+    )
+    .parse(text, position)?;
+    let (text, position, (v, errors)) = value_recovering(text, position)?;
+    Ok((text, position, (key, v, errors)))
+}
+
+/// The pairs a recovering parse managed to collect, alongside one [`ParseError`] per entry it had
+/// to skip over. Named for the same reason as [`ParsedOptionString`]: so the recovering functions
+/// don't each spell out the same nested `(Vec<(String, Value)>, Vec<ParseError>)` type.
+type RecoveredPairs = (Vec<(String, Value)>, Vec<ParseError>);
+
+/// Drives the `;`-separated resync loop shared by [`key_value_pairs_recovering`] and
+/// [`list_recovering`]: parses entries with `parse_entry` until `closing` (or the end of `text`)
+/// is reached, skipping a malformed entry to the next recognizable boundary via
+/// [`skip_to_boundary`] rather than aborting the whole parse. After a skipped entry, the `;`
+/// before the next one is optional — a resync can land directly on what looks like the next entry
+/// rather than on a literal `;` — but after two well-formed entries in a row it's required, same
+/// as the non-recovering grammar, and its absence is reported (without losing either entry) rather
+/// than silently accepted.
+fn recover_entries<'a, T>(
+    text: &'a str,
+    position: usize,
+    closing: char,
+    parse_entry: impl Fn(&'a str, usize) -> ParseResult<'a, (T, Vec<ParseError>)>,
+) -> ParseResult<'a, (Vec<T>, Vec<ParseError>)> {
+    let (mut text, mut position, _) = skip_white_space(text, position)?;
+    let mut items = vec![];
+    let mut errors = vec![];
+    // No separator is expected before the very first entry, and none is required after a
+    // skipped one — the resync already accounts for whatever sits between it and the next entry.
+    let mut had_separator = true;
+    let mut prev_was_skipped = true;
+
+    while !text.is_empty() && !text.starts_with(closing) {
+        let entry_was_skipped = match parse_entry(text, position) {
+            Ok((next_text, next_position, (item, inner_errors))) => {
+                if !had_separator && !prev_was_skipped {
+                    errors.push(ParseError::ExpectedLiteralNotFound(position, ";".to_string()));
+                }
+                items.push(item);
+                errors.extend(inner_errors);
+                text = next_text;
+                position = next_position;
+                false
+            }
+            Err(err) => {
+                let (next_text, next_position, span) = skip_to_boundary(text, position);
+                errors.push(ParseError::RecoveredEntry(span, Box::new(err)));
+                text = next_text;
+                position = next_position;
+                true
+            }
+        };
+        prev_was_skipped = entry_was_skipped;
+
+        (text, position, _) = skip_white_space(text, position)?;
+        had_separator = match match_literal(";").parse(text, position) {
+            Ok((next_text, next_position, _)) => {
+                (text, position, _) = skip_white_space(next_text, next_position)?;
+                true
+            }
+            Err(_) => false,
+        };
+    }
+
+    Ok((text, position, (items, errors)))
+}
+
+fn key_value_pairs_recovering(text: &str, position: usize) -> ParseResult<'_, RecoveredPairs> {
+    recover_entries(text, position, ')', |text, position| {
+        key_value_pair_recovering(text, position)
+            .map(|(text, position, (key, v, errors))| (text, position, ((key, v), errors)))
+    })
+}
+
+/// Consumes the closing `)`/`]` that ends an [`object_recovering`]/[`list_recovering`] body. If
+/// it's missing — `text` ran out, or what follows belongs to an enclosing list/object instead —
+/// that's recorded as a `RecoveredEntry` rather than failing outright and discarding every entry
+/// already recovered in `content` along with it.
+fn close_recovering<'a, T>(
+    text: &'a str,
+    position: usize,
+    closing: &'static str,
+    mut content: (Vec<T>, Vec<ParseError>),
+) -> ParseResult<'a, (Vec<T>, Vec<ParseError>)> {
+    let (text, position, _) = skip_white_space(text, position)?;
+    match match_literal(closing).parse(text, position) {
+        Ok((text, position, _)) => Ok((text, position, content)),
+        Err(_) => {
+            let (next_text, next_position, span) = skip_to_boundary(text, position);
+            content.1.push(ParseError::RecoveredEntry(
+                span,
+                Box::new(ParseError::ExpectedLiteralNotFound(position, closing.to_string())),
+            ));
+            Ok((next_text, next_position, content))
+        }
+    }
+}
+
+fn object_recovering(text: &str, position: usize) -> ParseResult<'_, RecoveredPairs> {
+    let (text, position, _) = match_literal("(").parse(text, position)?;
+    let (text, position, content) = key_value_pairs_recovering(text, position)?;
+    close_recovering(text, position, ")", content)
+}
+
+fn list_recovering(text: &str, position: usize) -> ParseResult<'_, (Vec<Value>, Vec<ParseError>)> {
+    let (text, position, _) = match_literal("[").parse(text, position)?;
+    let (text, position, content) = recover_entries(text, position, ']', value_recovering)?;
+    close_recovering(text, position, "]", content)
+}
+
+/// Like [`parse_option_string`], but a malformed `key_value_pair`/`value` is skipped up to the
+/// next `;` or closing bracket rather than aborting the whole parse, so a config tool can report
+/// every mistake in `text` in one pass. Entries skipped along the way, at any nesting depth, come
+/// back in the second element of the tuple. Text that doesn't even start like an option string is
+/// skipped and reported the same way, so the outer `Result` is always `Ok`.
+pub fn parse_option_string_recovering(text: &str) -> Result<RecoveredPairs, ParseError> {
+    let (mut text, mut position) = (text, 0);
+    let mut pairs = vec![];
+    let mut errors = vec![];
+
+    loop {
+        let (next_text, next_position, (more_pairs, more_errors)) =
+            key_value_pairs_recovering(text, position)?;
+        pairs.extend(more_pairs);
+        errors.extend(more_errors);
+        text = next_text;
+        position = next_position;
+
+        if text.is_empty() {
+            break;
+        }
+
+        // Unlike a nested `object_recovering`/`list_recovering` call, there's no enclosing `)`
+        // here to notice a stray one via `match_literal` — it's the only thing that stops
+        // `key_value_pairs_recovering` early besides running out of `text` — so skip past it and
+        // keep going instead of treating it as "entries are done" and losing everything after it.
+        let (next_text, next_position, span) = skip_to_boundary(text, position);
+        errors.push(ParseError::RecoveredEntry(
+            span,
+            Box::new(ParseError::ExpectedLiteralNotFound(position, ";".to_string())),
+        ));
+        text = next_text;
+        position = next_position;
+    }
+
+    Ok((pairs, errors))
 }
 
 #[cfg(test)]
@@ -301,9 +839,89 @@ mod tests {
         assert_eq!(output.2, "aßb'\\   ".to_string());
     }
 
+    #[test]
+    fn test_single_quoted_string_rejects_unknown_escape() {
+        let err = single_quoted_string("'ab\\xcd'", 0).unwrap_err();
+        assert!(matches!(err, ParseError::UnknownEscapedSymbol(_, 'x')));
+    }
+
+    #[test]
+    fn test_single_quoted_string_reports_premature_end_of_text() {
+        let err = single_quoted_string("'abc", 0).unwrap_err();
+        assert!(matches!(err, ParseError::PrematureEndOfText(_)));
+    }
+
+    #[test]
+    fn test_double_quoted_string() {
+        let output = double_quoted_string(r#""C:\\Users\\a";"#, 0).unwrap();
+        assert_eq!(output.0, ";");
+        assert_eq!(output.2, r"C:\Users\a".to_string());
+    }
+
+    #[test]
+    fn test_double_quoted_string_rejects_unknown_escape() {
+        let err = double_quoted_string(r#""ab\xcd""#, 0).unwrap_err();
+        assert!(matches!(err, ParseError::UnknownEscapedSymbol(_, 'x')));
+    }
+
+    #[test]
+    fn test_double_quoted_string_reports_premature_end_of_text() {
+        let err = double_quoted_string("\"abc", 0).unwrap_err();
+        assert!(matches!(err, ParseError::PrematureEndOfText(_)));
+    }
+
+    #[test]
+    fn test_escaped_char_supports_control_escapes() {
+        let output = single_quoted_string(r"'a\n\t\r\0b'", 0).unwrap();
+        assert_eq!(output.2, "a\n\t\r\0b".to_string());
+    }
+
+    #[test]
+    fn test_escaped_char_supports_unicode_escapes() {
+        let braced = single_quoted_string(r"'\u{1F600}'", 0).unwrap();
+        assert_eq!(braced.2, "\u{1F600}".to_string());
+
+        let fixed_width = single_quoted_string(r"'\u00e9'", 0).unwrap();
+        assert_eq!(fixed_width.2, "é".to_string());
+    }
+
+    #[test]
+    fn test_escaped_char_rejects_invalid_unicode_escapes() {
+        assert!(matches!(
+            single_quoted_string(r"'\u00e'", 0).unwrap_err(),
+            ParseError::InvalidUnicodeEscape(_)
+        ));
+        assert!(matches!(
+            single_quoted_string(r"'\u{}'", 0).unwrap_err(),
+            ParseError::InvalidUnicodeEscape(_)
+        ));
+        assert!(matches!(
+            single_quoted_string(r"'\u{ffffff}'", 0).unwrap_err(),
+            ParseError::InvalidUnicodeEscape(_)
+        ));
+    }
+
+    #[test]
+    fn test_quoted_string_dispatches_on_opening_quote() {
+        assert_eq!(quoted_string("'a'", 0).unwrap().2, "a".to_string());
+        assert_eq!(quoted_string("\"a\"", 0).unwrap().2, "a".to_string());
+    }
+
+    #[test]
+    fn test_value_accepts_either_quote_style() {
+        assert_eq!(
+            value("\"C:\\\\Users\\\\a\"", 0).unwrap().2,
+            Value::StringValue(r"C:\Users\a".to_string())
+        );
+        assert_eq!(
+            value("'a'", 0).unwrap().2,
+            Value::StringValue("a".to_string())
+        );
+    }
+
     #[test]
     fn test_key_value_pair() {
-        let output = key_value_pair("key='aßb\\\'\\\\   '   ", 0).unwrap();
+        let output = key_value_pair_with("key='aßb\\\'\\\\   '   ", 0, value).unwrap();
         assert_eq!(output.0, "   ");
         assert_eq!(
             output.2,
@@ -313,7 +931,7 @@ mod tests {
             )
         );
 
-        let output = key_value_pair("key = 'aßb\\\'\\\\   '   ", 0).unwrap();
+        let output = key_value_pair_with("key = 'aßb\\\'\\\\   '   ", 0, value).unwrap();
         assert_eq!(output.0, "   ");
         assert_eq!(
             output.2,
@@ -442,6 +1060,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_value_reports_no_value_found_on_empty_input() {
+        let err = value("", 0).unwrap_err();
+        assert!(matches!(err, ParseError::NoValueFound(0)));
+    }
+
+    #[test]
+    fn test_value_reports_no_value_found_for_unterminated_quoted_string() {
+        let err = key_value_pair_with("a='unterminated", 0, value).unwrap_err();
+        assert!(matches!(err, ParseError::NoValueFound(2)));
+    }
+
+    #[test]
+    fn test_value_recovering_reports_no_value_found_for_empty_entry_value() {
+        let err = key_value_pair_recovering("k=)", 0).unwrap_err();
+        assert!(matches!(err, ParseError::NoValueFound(2)));
+    }
+
+    #[test]
+    fn test_value_typed_reports_no_value_found_for_unterminated_quoted_string() {
+        let err = key_value_pair_with("a='unterminated", 0, value_typed).unwrap_err();
+        assert!(matches!(err, ParseError::NoValueFound(2)));
+    }
+
     #[test]
     fn test_valid_option_strings() {
         let valid_option_strings = vec![
@@ -490,4 +1132,262 @@ mod tests {
              */
         }
     }
+
+    #[test]
+    fn test_typed_scalar() {
+        assert_eq!(typed_scalar("true", 0).unwrap().2, Value::BoolValue(true));
+        assert_eq!(typed_scalar("false", 0).unwrap().2, Value::BoolValue(false));
+        assert_eq!(typed_scalar("10", 0).unwrap().2, Value::IntValue(10));
+        assert_eq!(typed_scalar("-10", 0).unwrap().2, Value::IntValue(-10));
+        assert_eq!(typed_scalar("1.5", 0).unwrap().2, Value::FloatValue(1.5));
+        assert_eq!(
+            typed_scalar("DoIP-Group", 0).unwrap().2,
+            Value::StringValue("DoIP-Group".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_option_string_typed() {
+        let output = parse_option_string_typed(
+            "maxFiles=10;CombinationMode='DoIP-Group';Udp13401=true",
+        )
+        .unwrap();
+        assert_eq!(
+            output.1,
+            vec![
+                ("maxFiles".to_string(), Value::IntValue(10)),
+                (
+                    "CombinationMode".to_string(),
+                    Value::StringValue("DoIP-Group".to_string())
+                ),
+                ("Udp13401".to_string(), Value::BoolValue(true)),
+            ]
+        );
+
+        let output =
+            parse_option_string_typed("options=(PreselectionMode=None;VehicleDiscoveryTime=100)")
+                .unwrap();
+        assert_eq!(
+            output.1,
+            vec![(
+                "options".to_string(),
+                Value::ObjectValue(vec![
+                    (
+                        "PreselectionMode".to_string(),
+                        Value::StringValue("None".to_string())
+                    ),
+                    ("VehicleDiscoveryTime".to_string(), Value::IntValue(100)),
+                ])
+            )]
+        );
+
+        let output = parse_option_string_typed("modules=[100;200]").unwrap();
+        assert_eq!(
+            output.1,
+            vec![(
+                "modules".to_string(),
+                Value::ListValue(vec![Value::IntValue(100), Value::IntValue(200)])
+            )]
+        );
+    }
+
+    #[test]
+    fn test_value_coercion_helpers() {
+        assert_eq!(Value::IntValue(10).as_i64(), Some(10));
+        assert_eq!(Value::StringValue("10".to_string()).as_i64(), Some(10));
+        assert_eq!(Value::StringValue("abc".to_string()).as_i64(), None);
+
+        assert_eq!(Value::BoolValue(true).as_bool(), Some(true));
+        assert_eq!(Value::StringValue("true".to_string()).as_bool(), Some(true));
+        assert_eq!(Value::IntValue(1).as_bool(), None);
+
+        assert_eq!(Value::StringValue("a".to_string()).as_str(), Some("a"));
+        assert_eq!(Value::IntValue(1).as_str(), None);
+    }
+
+    #[test]
+    fn test_line_col() {
+        let text = "a=1\nb=2\nc=);";
+        assert_eq!(line_col(text, 0), (1, 1));
+        assert_eq!(line_col(text, 4), (2, 1));
+        assert_eq!(line_col(text, 10), (3, 3));
+    }
+
+    #[test]
+    fn test_render_parse_error() {
+        let text = "key=(sub=')";
+        let err = parse_option_string(text).unwrap_err();
+        let rendered = render_parse_error(text, &err);
+        let (line, col) = line_col(text, err.position());
+        assert!(rendered.starts_with(&format!("{line}:{col}: ")));
+        assert!(rendered.contains("key=(sub=')"));
+        assert!(rendered.ends_with('^'));
+    }
+
+    #[test]
+    fn test_parse_option_string_recovering_skips_malformed_entries() {
+        let (pairs, errors) =
+            parse_option_string_recovering("a='1';b=while;c='3'").unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".to_string(), Value::StringValue("1".to_string())),
+                ("c".to_string(), Value::StringValue("3".to_string())),
+            ]
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ParseError::RecoveredEntry(_, _)));
+    }
+
+    #[test]
+    fn test_parse_option_string_recovering_recovers_nested_entries() {
+        let (pairs, errors) = parse_option_string_recovering(
+            "modules=[(name='a';type=while);(name='b';type='doip')]",
+        )
+        .unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            pairs,
+            vec![(
+                "modules".to_string(),
+                Value::ListValue(vec![
+                    Value::ObjectValue(vec![(
+                        "name".to_string(),
+                        Value::StringValue("a".to_string())
+                    )]),
+                    Value::ObjectValue(vec![
+                        ("name".to_string(), Value::StringValue("b".to_string())),
+                        ("type".to_string(), Value::StringValue("doip".to_string())),
+                    ]),
+                ])
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_option_string_recovering_recovers_entry_after_stray_top_level_bracket() {
+        let (pairs, errors) = parse_option_string_recovering("a='1')b='2'").unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ParseError::RecoveredEntry(_, _)));
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".to_string(), Value::StringValue("1".to_string())),
+                ("b".to_string(), Value::StringValue("2".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_option_string_recovering_reports_stray_trailing_text() {
+        let (pairs, errors) = parse_option_string_recovering("a='1'];b='2'").unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".to_string(), Value::StringValue("1".to_string())),
+                ("b".to_string(), Value::StringValue("2".to_string())),
+            ]
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ParseError::RecoveredEntry(_, _)));
+    }
+
+    #[test]
+    fn test_parse_option_string_recovering_reports_missing_separator_between_valid_entries() {
+        // Both entries are individually well-formed, so nothing triggers a resync — but the
+        // missing `;` between them still has to be reported, same as the non-recovering grammar.
+        let (pairs, errors) = parse_option_string_recovering("a='1'b='2'").unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".to_string(), Value::StringValue("1".to_string())),
+                ("b".to_string(), Value::StringValue("2".to_string())),
+            ]
+        );
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ParseError::ExpectedLiteralNotFound(_, _)));
+    }
+
+    #[test]
+    fn test_parse_option_string_recovering_recovers_entry_with_no_separator_before_it() {
+        // There's no `;` between the stray `]` and `c='2'`, so resync can't just hunt for the next
+        // delimiter — it has to recognize `c='2'` as a fresh entry to avoid swallowing it too.
+        let (pairs, errors) = parse_option_string_recovering("a='1']c='2'").unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ParseError::RecoveredEntry(_, _)));
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".to_string(), Value::StringValue("1".to_string())),
+                ("c".to_string(), Value::StringValue("2".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skip_to_boundary_ignores_separators_inside_quoted_strings() {
+        // `a`'s bare-word value is malformed, so the whole entry gets skipped to resync. The skip
+        // has to cross the embedded `;` inside the single-quoted fragment without stopping there,
+        // or `b` would never be reached.
+        let (pairs, errors) =
+            parse_option_string_recovering("a=x'semi;here'y;b='2'").unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ParseError::RecoveredEntry(_, _)));
+        assert_eq!(
+            pairs,
+            vec![("b".to_string(), Value::StringValue("2".to_string()))]
+        );
+    }
+
+    #[test]
+    fn test_parse_option_string_recovering_recovers_entry_after_malformed_one_with_no_separator() {
+        // No `;` separates `b`'s malformed value from the otherwise-valid `c` entry that follows
+        // it, so recovery can't rely on finding a literal separator before retrying: it has to
+        // resync straight onto `c='3'` instead of swallowing it along with `b`.
+        let (pairs, errors) = parse_option_string_recovering("a=while;b=oops c='3'").unwrap();
+        assert_eq!(errors.len(), 2);
+        assert!(errors
+            .iter()
+            .all(|e| matches!(e, ParseError::RecoveredEntry(_, _))));
+        assert_eq!(
+            pairs,
+            vec![("c".to_string(), Value::StringValue("3".to_string()))]
+        );
+    }
+
+    #[test]
+    fn test_parse_option_string_recovering_keeps_recovered_entries_despite_missing_bracket() {
+        let (pairs, errors) =
+            parse_option_string_recovering("modules=[(name='a');(name='b')c='next'").unwrap();
+        assert_eq!(
+            pairs,
+            vec![(
+                "modules".to_string(),
+                Value::ListValue(vec![
+                    Value::ObjectValue(vec![(
+                        "name".to_string(),
+                        Value::StringValue("a".to_string())
+                    )]),
+                    Value::ObjectValue(vec![(
+                        "name".to_string(),
+                        Value::StringValue("b".to_string())
+                    )]),
+                ])
+            )]
+        );
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_option_string_recovering_clean_input_has_no_errors() {
+        let (pairs, errors) = parse_option_string_recovering("a='1';b='2'").unwrap();
+        assert_eq!(
+            pairs,
+            vec![
+                ("a".to_string(), Value::StringValue("1".to_string())),
+                ("b".to_string(), Value::StringValue("2".to_string())),
+            ]
+        );
+        assert!(errors.is_empty());
+    }
 }
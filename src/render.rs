@@ -0,0 +1,296 @@
+//! Pluggable rendering of a parsed [`Value`] tree back into text, in the spirit of orgize's
+//! handler-plus-driver HTML export: a [`ValueHandler`] supplies the callbacks for each node kind
+//! and [`render`]/[`render_pairs`] walk the tree, calling them in order. This gives callers a
+//! `Value -> String` round trip ([`CanonicalHandler`]) and a bridge to other tooling
+//! ([`JsonHandler`]) without pulling in serde.
+
+use crate::Value;
+
+/// Callbacks invoked while walking a [`Value`] tree, in the order its text would read.
+///
+/// Every method appends to `out` rather than returning a piece of text, so a handler can stream
+/// straight into the caller's buffer instead of allocating and concatenating fragments.
+pub trait ValueHandler {
+    fn begin_object(&mut self, out: &mut String);
+    fn end_object(&mut self, out: &mut String);
+    fn begin_list(&mut self, out: &mut String);
+    fn end_list(&mut self, out: &mut String);
+    /// Called between two entries of the same object or list, but not before the first or after
+    /// the last.
+    fn separator(&mut self, out: &mut String);
+    /// Called for an object entry's key, immediately before rendering its value.
+    fn key(&mut self, key: &str, out: &mut String);
+    fn string(&mut self, s: &str, out: &mut String);
+    fn int(&mut self, n: i64, out: &mut String);
+    fn float(&mut self, n: f64, out: &mut String);
+    fn bool(&mut self, b: bool, out: &mut String);
+}
+
+/// Renders `value` by driving `handler`'s callbacks, appending to `out`.
+pub fn render(value: &Value, handler: &mut impl ValueHandler, out: &mut String) {
+    match value {
+        Value::StringValue(s) => handler.string(s, out),
+        Value::IntValue(n) => handler.int(*n, out),
+        Value::FloatValue(n) => handler.float(*n, out),
+        Value::BoolValue(b) => handler.bool(*b, out),
+        Value::ListValue(items) => {
+            handler.begin_list(out);
+            render_items(items, handler, out);
+            handler.end_list(out);
+        }
+        Value::ObjectValue(pairs) => {
+            handler.begin_object(out);
+            render_pairs(pairs, handler, out);
+            handler.end_object(out);
+        }
+    }
+}
+
+fn render_items(items: &[Value], handler: &mut impl ValueHandler, out: &mut String) {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            handler.separator(out);
+        }
+        render(item, handler, out);
+    }
+}
+
+/// Renders a flat sequence of `key=value` pairs, the shape [`parse_option_string`](crate::parse_option_string)
+/// and friends return for the top level of an option string (which, unlike a nested
+/// [`Value::ObjectValue`], isn't wrapped in `(...)`).
+pub fn render_pairs(pairs: &[(String, Value)], handler: &mut impl ValueHandler, out: &mut String) {
+    for (i, (key, value)) in pairs.iter().enumerate() {
+        if i > 0 {
+            handler.separator(out);
+        }
+        handler.key(key, out);
+        render(value, handler, out);
+    }
+}
+
+/// Renders `pairs` as a normalized option string using `handler`, returning the result.
+pub fn render_option_string(pairs: &[(String, Value)], handler: &mut impl ValueHandler) -> String {
+    let mut out = String::new();
+    render_pairs(pairs, handler, &mut out);
+    out
+}
+
+/// Writes `s` as a single-quoted string literal, escaping `'` and `\` the way
+/// [`single_quoted_string`](crate) expects to read them back.
+fn push_quoted(s: &str, out: &mut String) {
+    out.push('\'');
+    for c in s.chars() {
+        if c == '\'' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('\'');
+}
+
+/// Emits a normalized option string: quoted strings with `'`/`\` escaped, bare `true`/`false`
+/// and numbers, `key='value'` with no extra spacing, and `;` between entries. Nested
+/// [`Value::ObjectValue`]s are wrapped in `(...)`, [`Value::ListValue`]s in `[...]`, matching
+/// [`object`](crate)/[`list`](crate)'s grammar so the output parses right back with
+/// [`parse_option_string_typed`](crate::parse_option_string_typed).
+///
+/// Keys are written bare, like the grammar's [`identifier`](crate) production requires — there's
+/// no quoted-key syntax to fall back on. Every key this crate's own parsers produce is already a
+/// valid identifier, so this only matters for an `ObjectValue` built by hand with a key the
+/// grammar couldn't have parsed in the first place.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CanonicalHandler;
+
+impl ValueHandler for CanonicalHandler {
+    fn begin_object(&mut self, out: &mut String) {
+        out.push('(');
+    }
+
+    fn end_object(&mut self, out: &mut String) {
+        out.push(')');
+    }
+
+    fn begin_list(&mut self, out: &mut String) {
+        out.push('[');
+    }
+
+    fn end_list(&mut self, out: &mut String) {
+        out.push(']');
+    }
+
+    fn separator(&mut self, out: &mut String) {
+        out.push(';');
+    }
+
+    fn key(&mut self, key: &str, out: &mut String) {
+        out.push_str(key);
+        out.push('=');
+    }
+
+    fn string(&mut self, s: &str, out: &mut String) {
+        push_quoted(s, out);
+    }
+
+    fn int(&mut self, n: i64, out: &mut String) {
+        out.push_str(&n.to_string());
+    }
+
+    fn float(&mut self, n: f64, out: &mut String) {
+        let rendered = n.to_string();
+        out.push_str(&rendered);
+        // `f64::to_string` drops the fractional part for whole numbers (`1.0` -> `"1"`), which
+        // would parse back as an `IntValue` through `typed_scalar`'s int-before-float fallback.
+        // Forcing a `.0` keeps the round trip a `FloatValue`. Non-finite values (`NaN`/`inf`)
+        // already fail the int parse and round-trip through `f64::from_str` as-is.
+        if n.is_finite() && !rendered.contains(['.', 'e', 'E']) {
+            out.push_str(".0");
+        }
+    }
+
+    fn bool(&mut self, b: bool, out: &mut String) {
+        out.push_str(if b { "true" } else { "false" });
+    }
+}
+
+/// Writes `s` as a JSON string literal, escaping the characters JSON requires.
+fn push_json_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Renders a [`Value`] tree as JSON: objects become `{"key":value,...}`, lists become
+/// `[value,...]`, and scalars map onto their natural JSON counterpart. JSON has no token for
+/// non-finite floats, so a `NaN`/`inf`/`-inf` [`Value::FloatValue`] (reachable via
+/// [`parse_option_string_typed`](crate::parse_option_string_typed), whose bare-word scalars parse
+/// through `f64::from_str`) renders as `null`, the same convention most JSON serializers use.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonHandler;
+
+impl ValueHandler for JsonHandler {
+    fn begin_object(&mut self, out: &mut String) {
+        out.push('{');
+    }
+
+    fn end_object(&mut self, out: &mut String) {
+        out.push('}');
+    }
+
+    fn begin_list(&mut self, out: &mut String) {
+        out.push('[');
+    }
+
+    fn end_list(&mut self, out: &mut String) {
+        out.push(']');
+    }
+
+    fn separator(&mut self, out: &mut String) {
+        out.push(',');
+    }
+
+    fn key(&mut self, key: &str, out: &mut String) {
+        push_json_string(key, out);
+        out.push(':');
+    }
+
+    fn string(&mut self, s: &str, out: &mut String) {
+        push_json_string(s, out);
+    }
+
+    fn int(&mut self, n: i64, out: &mut String) {
+        out.push_str(&n.to_string());
+    }
+
+    fn float(&mut self, n: f64, out: &mut String) {
+        if n.is_finite() {
+            out.push_str(&n.to_string());
+        } else {
+            out.push_str("null");
+        }
+    }
+
+    fn bool(&mut self, b: bool, out: &mut String) {
+        out.push_str(if b { "true" } else { "false" });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_option_string_typed;
+
+    #[test]
+    fn test_canonical_handler_round_trips_through_parse_option_string_typed() {
+        let text = r"a='it\'s \\ fine';b=10;c=true;d=[1;2];e=(f='g')";
+        let (_, pairs) = parse_option_string_typed(text).unwrap();
+
+        let rendered = render_option_string(&pairs, &mut CanonicalHandler);
+        let (_, reparsed) = parse_option_string_typed(&rendered).unwrap();
+
+        assert_eq!(pairs, reparsed);
+    }
+
+    #[test]
+    fn test_canonical_handler_escapes_quotes_and_backslashes() {
+        let pairs = vec![("a".to_string(), Value::StringValue(r"it's a \test".to_string()))];
+        let rendered = render_option_string(&pairs, &mut CanonicalHandler);
+        assert_eq!(rendered, r"a='it\'s a \\test'");
+    }
+
+    #[test]
+    fn test_canonical_handler_round_trips_whole_number_floats() {
+        let pairs = vec![("a".to_string(), Value::FloatValue(1.0))];
+        let rendered = render_option_string(&pairs, &mut CanonicalHandler);
+        let (_, reparsed) = parse_option_string_typed(&rendered).unwrap();
+        assert_eq!(reparsed, pairs);
+    }
+
+    #[test]
+    fn test_json_handler_renders_non_finite_floats_as_null() {
+        let pairs = vec![
+            ("a".to_string(), Value::FloatValue(f64::NAN)),
+            ("b".to_string(), Value::FloatValue(f64::INFINITY)),
+        ];
+        let mut out = String::new();
+        render_pairs(&pairs, &mut JsonHandler, &mut out);
+        assert_eq!(out, r#""a":null,"b":null"#);
+    }
+
+    #[test]
+    fn test_json_handler_renders_nested_values() {
+        let pairs = vec![
+            ("a".to_string(), Value::StringValue("x".to_string())),
+            (
+                "b".to_string(),
+                Value::ObjectValue(vec![("c".to_string(), Value::IntValue(1))]),
+            ),
+            (
+                "d".to_string(),
+                Value::ListValue(vec![Value::BoolValue(true), Value::FloatValue(1.5)]),
+            ),
+        ];
+
+        let mut out = String::new();
+        render_pairs(&pairs, &mut JsonHandler, &mut out);
+
+        assert_eq!(out, r#""a":"x","b":{"c":1},"d":[true,1.5]"#);
+    }
+
+    #[test]
+    fn test_json_handler_escapes_control_characters() {
+        let pairs = vec![("a".to_string(), Value::StringValue("line\nbreak\t\"quote\"".to_string()))];
+        let mut out = String::new();
+        render_pairs(&pairs, &mut JsonHandler, &mut out);
+        assert_eq!(out, "\"a\":\"line\\nbreak\\t\\\"quote\\\"\"");
+    }
+}
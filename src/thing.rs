@@ -19,7 +19,7 @@ macro_rules! pub_extern {
         #[cfg(not(target_arch = "x86"))]
         $(#[$attr])*
         #[no_mangle]
-        pub extern fn $name($($param: $param_type),*) -> $return_type {
+        pub extern "C" fn $name($($param: $param_type),*) -> $return_type {
             $body
         }
     };